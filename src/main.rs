@@ -1,24 +1,33 @@
+mod preprocessor;
+
 use clap::Parser;
 use futures::executor::block_on;
 use notify::{ReadDirectoryChangesWatcher, Watcher};
 use std::{
     borrow::Cow,
-    fs::{read_to_string, OpenOptions},
+    collections::HashSet,
+    fs::OpenOptions,
     io::Write,
     path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 use wgpu::{
     util::{BufferInitDescriptor, DeviceExt},
-    Adapter, Backends, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor,
-    BindGroupLayoutEntry, BufferBindingType, BufferUsages, CommandEncoderDescriptor,
-    CompositeAlphaMode, Device, DeviceDescriptor, Features, Instance, Limits, LoadOp, Operations,
-    PipelineLayout, PrimitiveState, Queue, RenderPassColorAttachment, RenderPassDescriptor,
-    RenderPipeline, RequestAdapterOptions, ShaderModule, ShaderSource, ShaderStages, Surface,
-    SurfaceConfiguration, TextureFormat,
+    Adapter, AddressMode, Backends, BindGroup, BindGroupDescriptor, BindGroupEntry,
+    BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, Buffer, BufferBindingType,
+    BufferUsages, CommandEncoderDescriptor, CompositeAlphaMode, Device, DeviceDescriptor, Extent3d,
+    Features, FilterMode, Instance, Limits, LoadOp, Operations, PipelineLayout, PrimitiveState,
+    Queue, RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, RequestAdapterOptions,
+    Sampler, SamplerDescriptor, ShaderModule, ShaderSource, ShaderStages, Surface,
+    SurfaceConfiguration, Texture, TextureDescriptor, TextureDimension, TextureFormat,
+    TextureUsages, TextureView, TextureViewDescriptor,
 };
 use winit::{dpi::PhysicalSize, event::WindowEvent, event_loop::EventLoopProxy, window::Window};
 use winit::{event::Event::UserEvent, event_loop::EventLoop};
+use winit::{
+    keyboard::{KeyCode, PhysicalKey},
+    window::Fullscreen,
+};
 
 #[derive(Debug)]
 enum UserEvents {
@@ -28,52 +37,373 @@ enum UserEvents {
 
 #[derive(Parser)]
 struct Opts {
+    /// The final "image" pass, drawn to the swapchain.
     wgsl_file: PathBuf,
 
+    /// An offscreen buffer pass, rendered before the image pass in the order given.
+    /// Buffer passes can sample the previous frame's output of any buffer (including
+    /// their own, for feedback effects) through bind group 1, one texture/sampler pair per
+    /// buffer in declaration order.
+    #[clap(long = "buffer")]
+    buffers: Vec<PathBuf>,
+
+    /// An image to load as a texture channel (iChannel0, iChannel1, ... in declaration
+    /// order), sampled by every pass. Replacing the file on disk hot-reloads it.
+    #[clap(long = "channel")]
+    channels: Vec<PathBuf>,
+
     #[clap(short, long)]
     create: bool,
 
     #[clap(short, long)]
     always_on_top: bool,
+
+    /// Graphics backend to request. Defaults to letting wgpu pick among all available
+    /// backends on this platform.
+    #[clap(long, value_enum, default_value = "auto")]
+    backend: BackendArg,
+
+    /// Path to dxcompiler.dll. Only takes effect together with --dxil-path; otherwise the
+    /// DX12 backend falls back to the FXC shader compiler.
+    #[clap(long)]
+    dxc_path: Option<PathBuf>,
+
+    /// Path to dxil.dll. Only takes effect together with --dxc-path.
+    #[clap(long)]
+    dxil_path: Option<PathBuf>,
+
+    /// Request WebGL2-compatible downlevel limits, to validate shaders against
+    /// constrained targets.
+    #[clap(long)]
+    downlevel: bool,
+
+    /// Render offline to PNG frames in this directory instead of opening a window.
+    /// Bypasses the swapchain entirely, so it also works without a display attached.
+    #[clap(long)]
+    render_to: Option<PathBuf>,
+
+    /// Number of frames to render in --render-to mode.
+    #[clap(long, default_value_t = 60)]
+    frames: u32,
+
+    /// Frame rate used to derive `time`/`time_delta` for each frame in --render-to mode.
+    #[clap(long, default_value_t = 30.0)]
+    fps: f32,
+
+    /// Output size for --render-to mode, as WIDTHxHEIGHT.
+    #[clap(long, value_parser = parse_size, default_value = "600x600")]
+    size: (u32, u32),
+}
+
+/// Parses a `WIDTHxHEIGHT` size, as accepted by `--size`.
+fn parse_size(s: &str) -> Result<(u32, u32), String> {
+    let (width, height) = s
+        .split_once('x')
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {:?}", s))?;
+    let width = width
+        .parse()
+        .map_err(|_| format!("invalid width in {:?}", s))?;
+    let height = height
+        .parse()
+        .map_err(|_| format!("invalid height in {:?}", s))?;
+    Ok((width, height))
 }
 
-#[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+#[derive(Copy, Clone, Debug, clap::ValueEnum)]
+enum BackendArg {
+    Vulkan,
+    Metal,
+    Dx12,
+    Gl,
+    Auto,
+}
+
+impl BackendArg {
+    fn to_backends(self) -> Backends {
+        match self {
+            BackendArg::Vulkan => Backends::VULKAN,
+            BackendArg::Metal => Backends::METAL,
+            BackendArg::Dx12 => Backends::DX12,
+            BackendArg::Gl => Backends::GL,
+            BackendArg::Auto => Backends::all(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, encase::ShaderType)]
 struct Uniforms {
-    pub mouse: [f32; 2],
+    /// xy: current cursor position. zw: position of the last left-button press; the sign
+    /// of z flips positive while the button is held down, negative once released (mirrors
+    /// Shadertoy's iMouse).
+    pub mouse: glam::Vec4,
     pub time: f32,
-    pub pad: f32,
-    pub window_size: [f32; 2],
+    pub time_delta: f32,
+    pub frame: u32,
+    pub resolution: glam::Vec2,
 }
 
 impl Default for Uniforms {
     fn default() -> Uniforms {
         Uniforms {
+            mouse: glam::Vec4::ZERO,
             time: 0.,
-            mouse: [0.0, 0.0],
-            pad: 0.,
-            window_size: [0., 0.],
+            time_delta: 0.,
+            frame: 0,
+            resolution: glam::Vec2::ZERO,
         }
     }
 }
 
 impl Uniforms {
-    fn as_bytes(&self) -> &[u8] {
-        bytemuck::bytes_of(self)
+    fn as_bytes(&self) -> Vec<u8> {
+        let mut buffer = encase::UniformBuffer::new(Vec::new());
+        buffer.write(self).unwrap();
+        buffer.into_inner()
+    }
+}
+
+/// Creates a 2D texture of `size` with the given `usage`. Shared by `BufferPass` (whose
+/// front/back textures only need to be sampled) and `Playground::render_target` (which
+/// `render_frame` draws into, and which also needs `COPY_SRC` so it can be read back or
+/// blitted to the swapchain).
+fn create_texture(
+    device: &Device,
+    format: TextureFormat,
+    size: PhysicalSize<u32>,
+    usage: TextureUsages,
+) -> (Texture, TextureView) {
+    let texture = device.create_texture(&TextureDescriptor {
+        label: None,
+        size: Extent3d {
+            width: size.width.max(1),
+            height: size.height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format,
+        usage,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&TextureViewDescriptor::default());
+    (texture, view)
+}
+
+/// An offscreen pass that renders into a double-buffered texture, so the next frame can
+/// sample the previous frame's output of any buffer (including itself, for feedback).
+struct BufferPass {
+    shader_path: PathBuf,
+    pipeline: RenderPipeline,
+    front: (Texture, TextureView),
+    back: (Texture, TextureView),
+}
+
+impl BufferPass {
+    fn new(
+        device: &Device,
+        vertex_shader_module: &ShaderModule,
+        pipeline_layout: &PipelineLayout,
+        format: TextureFormat,
+        size: PhysicalSize<u32>,
+        shader_path: PathBuf,
+    ) -> Result<(BufferPass, HashSet<PathBuf>), String> {
+        let (pipeline, touched) = Playground::create_pipeline(
+            device,
+            vertex_shader_module,
+            pipeline_layout,
+            format,
+            &shader_path,
+        )?;
+
+        let usage = TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING;
+        Ok((
+            BufferPass {
+                shader_path,
+                pipeline,
+                front: create_texture(device, format, size, usage),
+                back: create_texture(device, format, size, usage),
+            },
+            touched,
+        ))
+    }
+
+    fn resize(&mut self, device: &Device, format: TextureFormat, size: PhysicalSize<u32>) {
+        let usage = TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING;
+        self.front = create_texture(device, format, size, usage);
+        self.back = create_texture(device, format, size, usage);
+    }
+
+    /// Swaps front and back so the buffer just rendered into `back` becomes next frame's
+    /// `front` (the previous-frame output that passes sample from).
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+}
+
+/// Builds a bind group layout exposing `count` `texture_2d<f32>` + `sampler` pairs,
+/// back to back starting at binding 0. Shared by the buffers group (previous frame's
+/// output of each buffer pass) and the channels group (static image channels).
+fn paired_texture_bind_group_layout(device: &Device, count: usize) -> BindGroupLayout {
+    let mut entries = Vec::with_capacity(count * 2);
+    for i in 0..count {
+        entries.push(BindGroupLayoutEntry {
+            binding: i as u32 * 2,
+            visibility: ShaderStages::FRAGMENT,
+            count: None,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+        });
+        entries.push(BindGroupLayoutEntry {
+            binding: i as u32 * 2 + 1,
+            visibility: ShaderStages::FRAGMENT,
+            count: None,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        });
+    }
+    device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+        label: None,
+        entries: &entries,
+    })
+}
+
+/// Builds the bind group sampling every buffer's current front (previous-frame) texture.
+fn buffers_bind_group(
+    device: &Device,
+    layout: &BindGroupLayout,
+    sampler: &Sampler,
+    buffers: &[BufferPass],
+) -> BindGroup {
+    let mut entries = Vec::with_capacity(buffers.len() * 2);
+    for (i, buffer) in buffers.iter().enumerate() {
+        entries.push(BindGroupEntry {
+            binding: i as u32 * 2,
+            resource: wgpu::BindingResource::TextureView(&buffer.front.1),
+        });
+        entries.push(BindGroupEntry {
+            binding: i as u32 * 2 + 1,
+            resource: wgpu::BindingResource::Sampler(sampler),
+        });
+    }
+    device.create_bind_group(&BindGroupDescriptor {
+        label: None,
+        layout,
+        entries: &entries,
+    })
+}
+
+/// An image loaded as a sampled texture channel (iChannel0, iChannel1, ...).
+struct Channel {
+    path: PathBuf,
+    texture: Texture,
+    view: TextureView,
+}
+
+impl Channel {
+    fn load(device: &Device, queue: &Queue, path: PathBuf) -> Result<Channel, String> {
+        let image = image::open(&path)
+            .map_err(|e| format!("could not load channel image {:?}: {}", path, e))?
+            .to_rgba8();
+        let (width, height) = image.dimensions();
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let texture = device.create_texture(&TextureDescriptor {
+            label: None,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &image,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        Ok(Channel {
+            path,
+            texture,
+            view,
+        })
     }
 }
 
+/// Builds the bind group sampling every channel's texture, in declaration order.
+fn channels_bind_group(
+    device: &Device,
+    layout: &BindGroupLayout,
+    sampler: &Sampler,
+    channels: &[Channel],
+) -> BindGroup {
+    let mut entries = Vec::with_capacity(channels.len() * 2);
+    for (i, channel) in channels.iter().enumerate() {
+        entries.push(BindGroupEntry {
+            binding: i as u32 * 2,
+            resource: wgpu::BindingResource::TextureView(&channel.view),
+        });
+        entries.push(BindGroupEntry {
+            binding: i as u32 * 2 + 1,
+            resource: wgpu::BindingResource::Sampler(sampler),
+        });
+    }
+    device.create_bind_group(&BindGroupDescriptor {
+        label: None,
+        layout,
+        entries: &entries,
+    })
+}
+
 struct Playground<'window> {
-    watch_path: PathBuf,
+    image_shader_path: PathBuf,
     render_pipeline: RenderPipeline,
-    window: &'window Window,
+    buffers: Vec<BufferPass>,
+    channels: Vec<Channel>,
+    sampler: Sampler,
+    buffers_bind_group_layout: BindGroupLayout,
+    channels_bind_group_layout: BindGroupLayout,
+    channels_bind_group: BindGroup,
+    // `None` in --render-to mode, which renders offscreen without a window or swapchain.
+    window: Option<&'window Window>,
     device: Device,
+    queue: Queue,
     vertex_shader_module: ShaderModule,
     pipeline_layout: PipelineLayout,
-    swapchain_format: TextureFormat,
-    surface_config: SurfaceConfiguration,
-    surface: Surface<'window>,
+    render_format: TextureFormat,
+    surface_config: Option<SurfaceConfiguration>,
+    surface: Option<Surface<'window>>,
+    watcher: Option<ReadDirectoryChangesWatcher>,
+    watched_paths: HashSet<PathBuf>,
+
+    // The offscreen target `render_frame` renders into, `COPY_SRC` so it can be read back
+    // or blitted. Kept across frames and only recreated on `resize`, like the BufferPass
+    // front/back textures, instead of allocating fresh every frame.
+    render_target: (Texture, TextureView),
 
+    uniforms_buffer: Buffer,
+    uniforms_buffer_bind_group: BindGroup,
     uniforms: Uniforms,
 }
 
@@ -81,13 +411,31 @@ impl<'window> Playground<'window> {
     fn reload(&mut self) {
         println!("Reload.");
 
-        self.recreate_pipeline();
+        self.recreate_pipelines();
+        self.reload_channels();
 
-        self.window.request_redraw();
+        if let Some(window) = self.window {
+            window.request_redraw();
+        }
+    }
+
+    fn reload_channels(&mut self) {
+        for channel in &mut self.channels {
+            match Channel::load(&self.device, &self.queue, channel.path.clone()) {
+                Ok(reloaded) => *channel = reloaded,
+                Err(e) => println!("{}", e),
+            }
+        }
+        self.channels_bind_group = channels_bind_group(
+            &self.device,
+            &self.channels_bind_group_layout,
+            &self.sampler,
+            &self.channels,
+        );
     }
 
     fn listen(
-        watch_path: PathBuf,
+        watched_paths: &HashSet<PathBuf>,
         proxy: EventLoopProxy<UserEvents>,
     ) -> ReadDirectoryChangesWatcher {
         let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
@@ -101,31 +449,40 @@ impl<'window> Playground<'window> {
             }
         })
         .unwrap();
-        watcher
-            .watch(&watch_path, notify::RecursiveMode::NonRecursive)
-            .unwrap();
+        for path in watched_paths {
+            watcher
+                .watch(path, notify::RecursiveMode::NonRecursive)
+                .unwrap();
+        }
         watcher
     }
 
     async fn get_async_stuff(
         instance: &Instance,
-        surface: &Surface<'_>,
+        compatible_surface: Option<&Surface<'_>>,
+        downlevel: bool,
     ) -> (Adapter, Device, Queue) {
         let adapter = instance
             .request_adapter(&RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(surface),
+                compatible_surface,
                 force_fallback_adapter: false,
             })
             .await
             .unwrap();
 
+        let required_limits = if downlevel {
+            Limits::downlevel_webgl2_defaults()
+        } else {
+            Limits::default()
+        };
+
         let (device, queue) = adapter
             .request_device(
                 &DeviceDescriptor {
                     label: None,
                     required_features: Features::empty(),
-                    required_limits: Limits::default(),
+                    required_limits,
                     memory_hints: wgpu::MemoryHints::Performance,
                 },
                 None,
@@ -136,17 +493,56 @@ impl<'window> Playground<'window> {
         (adapter, device, queue)
     }
 
-    fn recreate_pipeline(&mut self) {
+    fn recreate_pipelines(&mut self) {
+        let mut touched = HashSet::new();
+
         match Self::create_pipeline(
             &self.device,
             &self.vertex_shader_module,
             &self.pipeline_layout,
-            self.swapchain_format,
-            &self.watch_path,
+            self.render_format,
+            &self.image_shader_path,
         ) {
-            Ok(render_pipeline) => self.render_pipeline = render_pipeline,
+            Ok((render_pipeline, image_touched)) => {
+                self.render_pipeline = render_pipeline;
+                touched.extend(image_touched);
+            }
             Err(e) => println!("{}", e),
         }
+
+        for buffer in &mut self.buffers {
+            match Self::create_pipeline(
+                &self.device,
+                &self.vertex_shader_module,
+                &self.pipeline_layout,
+                self.render_format,
+                &buffer.shader_path,
+            ) {
+                Ok((pipeline, buffer_touched)) => {
+                    buffer.pipeline = pipeline;
+                    touched.extend(buffer_touched);
+                }
+                Err(e) => println!("{}", e),
+            }
+        }
+
+        self.watch_new_includes(&touched);
+    }
+
+    /// Registers any path in `touched` that isn't already being watched, so that newly
+    /// added `#include`s start triggering reloads without requiring a restart. A no-op
+    /// in --render-to mode, which has no watcher since it never reloads.
+    fn watch_new_includes(&mut self, touched: &HashSet<PathBuf>) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+        for path in touched {
+            if self.watched_paths.insert(path.clone()) {
+                if let Err(e) = watcher.watch(path, notify::RecursiveMode::NonRecursive) {
+                    println!("Could not watch {:?}: {}", path, e);
+                }
+            }
+        }
     }
 
     fn create_pipeline(
@@ -155,251 +551,439 @@ impl<'window> Playground<'window> {
         pipeline_layout: &PipelineLayout,
         swapchain_format: TextureFormat,
         frag_shader_path: &Path,
-    ) -> Result<RenderPipeline, String> {
-        let frag_wgsl = loop {
-            match read_to_string(frag_shader_path) {
-                Ok(s) => break s,
-                Err(_) => {
-                    // If file is being overwritten, white until it is available
-                    spin_sleep::sleep(Duration::from_millis(200));
-                }
-            }
-        };
+    ) -> Result<(RenderPipeline, HashSet<PathBuf>), String> {
+        let (frag_wgsl, touched) = preprocessor::flatten(frag_shader_path)?;
 
         let fragement_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Fragment shader"),
             source: ShaderSource::Wgsl(Cow::Owned(frag_wgsl)),
         });
 
-        Ok(
-            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: None,
-                layout: Some(pipeline_layout),
-                vertex: wgpu::VertexState {
-                    module: vertex_shader_module,
-                    entry_point: Some("vs_main"),
-                    buffers: &[],
-                    compilation_options: Default::default(),
-                },
-                primitive: PrimitiveState::default(),
-                depth_stencil: None,
-                multisample: wgpu::MultisampleState::default(),
-                multiview: None,
-                fragment: Some(wgpu::FragmentState {
-                    module: &fragement_shader_module,
-                    entry_point: Some("fs_main"),
-                    targets: &[Some(swapchain_format.into())],
-                    compilation_options: Default::default(),
-                }),
-                cache: None,
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: vertex_shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            fragment: Some(wgpu::FragmentState {
+                module: &fragement_shader_module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(swapchain_format.into())],
+                compilation_options: Default::default(),
             }),
-        )
-    }
-
-    pub fn resize(&mut self, new_size: &PhysicalSize<u32>) {
-        if new_size.width > 0 && new_size.height > 0 {
-            self.surface_config.width = new_size.width;
-            self.surface_config.height = new_size.height;
+            cache: None,
+        });
 
-            self.surface.configure(&self.device, &self.surface_config);
-            let logical_size = new_size.to_logical(self.window.scale_factor());
-            self.uniforms.window_size = [logical_size.width, logical_size.height];
-            self.window.request_redraw();
-        }
+        Ok((render_pipeline, touched))
     }
 
-    pub fn run(opts: &Opts) {
-        let event_loop = EventLoop::<UserEvents>::with_user_event().build().unwrap();
-        let proxy = event_loop.create_proxy();
+    /// Renders one frame of every buffer pass followed by the image pass itself (all of
+    /// them, the image pass included, sample the buffers' previous-frame output), into
+    /// `self.render_target`, which keeps its
+    /// `COPY_SRC`-usage texture across calls (recreated only on `resize`, like the
+    /// `BufferPass` front/back textures) instead of allocating a fresh one every frame.
+    /// Shared by the live window (which blits the target into the swapchain) and
+    /// --render-to (which reads it back to a PNG), so the two paths produce pixel-identical
+    /// output.
+    fn render_frame(&mut self, time: f32, frame: u32, size: PhysicalSize<u32>) -> &Texture {
+        self.uniforms.time = time;
+        self.uniforms.frame = frame;
+        self.uniforms.resolution = glam::Vec2::new(size.width as f32, size.height as f32);
+        self.queue
+            .write_buffer(&self.uniforms_buffer, 0, &self.uniforms.as_bytes());
 
-        let watch_path = opts.wgsl_file.clone();
-        let _watcher = Self::listen(watch_path, proxy);
+        let (target, target_view) = &self.render_target;
 
-        let window_attrs = Window::default_attributes()
-            .with_inner_size(PhysicalSize::new(600, 600))
-            .with_title("WGSL Playground");
-        let window = event_loop.create_window(window_attrs).unwrap();
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor { label: None });
 
-        let size = window.inner_size();
+        // Every pass this frame samples the buffers' outputs from the previous completed
+        // frame, so the bind group is built once, before any of this frame's passes write
+        // their back buffers.
+        let buffers_bind_group = buffers_bind_group(
+            &self.device,
+            &self.buffers_bind_group_layout,
+            &self.sampler,
+            &self.buffers,
+        );
 
-        window.set_window_level(winit::window::WindowLevel::AlwaysOnTop);
+        for buffer in &self.buffers {
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &buffer.back.1,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&buffer.pipeline);
+            render_pass.set_bind_group(0, &self.uniforms_buffer_bind_group, &[]);
+            render_pass.set_bind_group(1, &buffers_bind_group, &[]);
+            render_pass.set_bind_group(2, &self.channels_bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
 
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: Backends::DX12,
-            dx12_shader_compiler: wgpu::Dx12Compiler::Dxc {
-                dxil_path: Some("C:\\Users\\George\\bin\\dxc\\bin\\x86\\dxil.dll".into()),
-                dxc_path: Some("C:\\Users\\George\\bin\\dxc\\bin\\x86\\dxcompiler.dll".into()),
-            },
-            ..Default::default()
-        });
-        let surface = instance.create_surface(&window).unwrap();
-        let (adapter, device, queue) = block_on(Self::get_async_stuff(&instance, &surface));
+        {
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: target_view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &self.uniforms_buffer_bind_group, &[]);
+            render_pass.set_bind_group(1, &buffers_bind_group, &[]);
+            render_pass.set_bind_group(2, &self.channels_bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
 
-        let mut error_state = false;
+        self.queue.submit(std::iter::once(encoder.finish()));
 
-        // Handle errors
-        let proxy = event_loop.create_proxy();
-        device.on_uncaptured_error(Box::new(move |error| {
-            // Sending the event will stop the redraw
-            proxy.send_event(UserEvents::WGPUError).unwrap();
-            if let wgpu::Error::Validation {
-                source: _,
-                description,
-            } = error
-            {
-                if let Some(_) = description.find("note: label = `Fragment shader`") {
-                    println!("{}", description);
-                }
-            } else {
-                println!("{}", error);
-            }
-        }));
+        for buffer in &mut self.buffers {
+            buffer.swap();
+        }
 
-        let vertex_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Vertex shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("./vertex.wgsl").into()),
-        });
+        target
+    }
 
-        let uniforms = Uniforms::default();
+    /// Reads a `texture` created with `COPY_SRC` back into an RGBA image, blocking until
+    /// the GPU copy completes. Shared by --render-to (one read per exported frame) and the
+    /// live window's screenshot key. `self.render_format` must be an 8-bit-per-channel
+    /// format; BGRA variants are swizzled back into RGBA order.
+    fn read_back(&self, texture: &Texture, size: PhysicalSize<u32>) -> image::RgbaImage {
+        let bytes_per_pixel = 4;
+        let unpadded_bytes_per_row = size.width * bytes_per_pixel;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
 
-        let uniforms_buffer = device.create_buffer_init(&BufferInitDescriptor {
+        let staging_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
             label: None,
-            contents: uniforms.as_bytes(),
-            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            size: (padded_bytes_per_row * size.height) as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
         });
 
-        let uniforms_buffer_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: None,
-            entries: &[BindGroupLayoutEntry {
-                binding: 0,
-                visibility: ShaderStages::FRAGMENT,
-                count: None,
-                ty: wgpu::BindingType::Buffer {
-                    ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor { label: None });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &staging_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(size.height),
                 },
-            }],
-        });
+            },
+            Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
 
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: None,
-            bind_group_layouts: &[&uniforms_buffer_layout],
-            push_constant_ranges: &[],
+        let slice = staging_buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            tx.send(result).unwrap();
         });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv().unwrap().unwrap();
 
-        let caps = surface.get_capabilities(&adapter);
-        let swapchain_format = caps.formats[0];
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * size.height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        staging_buffer.unmap();
 
-        let render_pipeline = match Self::create_pipeline(
-            &device,
-            &vertex_shader_module,
-            &pipeline_layout,
-            swapchain_format,
-            &opts.wgsl_file,
+        if matches!(
+            self.render_format,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb
         ) {
-            Ok(render_pipeline) => render_pipeline,
-            Err(e) => {
-                println!("Could not start due to error: {}", &e);
-                return;
+            for pixel in pixels.chunks_mut(4) {
+                pixel.swap(0, 2);
             }
+        }
+
+        image::RgbaImage::from_raw(size.width, size.height, pixels)
+            .expect("staging buffer readback size matches the target texture")
+    }
+
+    /// No-op in --render-to mode, which has no window or surface to resize.
+    pub fn resize(&mut self, new_size: &PhysicalSize<u32>) {
+        let (Some(surface), Some(surface_config)) = (&self.surface, &mut self.surface_config)
+        else {
+            return;
         };
 
+        if new_size.width > 0 && new_size.height > 0 {
+            surface_config.width = new_size.width;
+            surface_config.height = new_size.height;
+
+            surface.configure(&self.device, surface_config);
+
+            for buffer in &mut self.buffers {
+                buffer.resize(&self.device, self.render_format, *new_size);
+            }
+            self.render_target = create_texture(
+                &self.device,
+                self.render_format,
+                *new_size,
+                TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            );
+
+            self.uniforms.resolution =
+                glam::Vec2::new(new_size.width as f32, new_size.height as f32);
+            if let Some(window) = self.window {
+                window.request_redraw();
+            }
+        }
+    }
+
+    pub fn run(opts: &Opts) {
+        if let Some(dir) = &opts.render_to {
+            Self::render_offline(opts, dir);
+            return;
+        }
+
+        let event_loop = EventLoop::<UserEvents>::with_user_event().build().unwrap();
+
+        let window_attrs = Window::default_attributes()
+            .with_inner_size(PhysicalSize::new(600, 600))
+            .with_title("WGSL Playground");
+        let window = event_loop.create_window(window_attrs).unwrap();
+
+        let size = window.inner_size();
+
+        if opts.always_on_top {
+            window.set_window_level(winit::window::WindowLevel::AlwaysOnTop);
+        }
+
+        let mut playground = match Self::build(opts, Some(&window), size) {
+            Some(playground) => playground,
+            None => return,
+        };
+
+        // Handle errors
+        let proxy = event_loop.create_proxy();
+        let error_proxy = proxy.clone();
+        playground
+            .device
+            .on_uncaptured_error(Box::new(move |error| {
+                // Sending the event will stop the redraw
+                error_proxy.send_event(UserEvents::WGPUError).unwrap();
+                if let wgpu::Error::Validation {
+                    source: _,
+                    description,
+                } = error
+                {
+                    if let Some(_) = description.find("note: label = `Fragment shader`") {
+                        println!("{}", description);
+                    }
+                } else {
+                    println!("{}", error);
+                }
+            }));
+
         let surface_config = SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: swapchain_format,
+            // COPY_DST so render_frame's offscreen target can be blitted in every frame.
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST,
+            format: playground.render_format,
             width: size.width,
             height: size.height,
             present_mode: wgpu::PresentMode::Immediate,
             alpha_mode: CompositeAlphaMode::Auto,
-            view_formats: vec![swapchain_format],
+            view_formats: vec![playground.render_format],
             desired_maximum_frame_latency: 2,
         };
 
-        surface.configure(&device, &surface_config);
+        playground
+            .surface
+            .as_ref()
+            .expect("build() creates a surface when given Some(window)")
+            .configure(&playground.device, &surface_config);
+        playground.surface_config = Some(surface_config);
 
-        let uniforms_buffer_bind_group = device.create_bind_group(&BindGroupDescriptor {
-            label: None,
-            layout: &uniforms_buffer_layout,
-            entries: &[BindGroupEntry {
-                binding: 0,
-                resource: uniforms_buffer.as_entire_binding(),
-            }],
-        });
+        playground.watcher = Some(Self::listen(&playground.watched_paths, proxy));
 
-        let mut playground = Playground {
-            watch_path: opts.wgsl_file.clone(),
-            render_pipeline,
-            window: &window,
-            device,
-            swapchain_format,
-            pipeline_layout,
-            vertex_shader_module,
-            surface_config,
-            surface,
-            uniforms,
-        };
+        let mut error_state = false;
 
         let frame_time = Duration::from_nanos(16_666_667); // Approximately 60 FPS
         let mut last_frame_time = Instant::now();
 
-        let instant = Instant::now();
+        const SCRUB_STEP_SECS: f32 = 1. / 30.;
+        let mut paused = false;
+        // Only meaningful while `paused`; the frozen value of `instant.elapsed()`.
+        let mut paused_time = 0.;
+        let mut take_screenshot = false;
+        // Starts at 0 like render_offline's `0..opts.frames`, so the two "pixel-identical"
+        // paths agree on iFrame numbering (uniforms.frame is only ever written, not read).
+        let mut frame_counter: u32 = 0;
+
+        let mut instant = Instant::now();
         if let Err(e) = event_loop.run(move |event, window_target| match event {
             winit::event::Event::WindowEvent { ref event, .. } => {
                 match event {
                     WindowEvent::CloseRequested => window_target.exit(),
                     WindowEvent::Resized(new_size) => playground.resize(new_size),
                     WindowEvent::CursorMoved { position, .. } => {
-                        let size = playground.window.inner_size();
-                        let normalized_x = position.x as f32 / size.width as f32;
-                        let normalized_y = position.y as f32 / size.height as f32;
-                        playground.uniforms.mouse =
-                            [normalized_x * 2. - 1., -normalized_y * 2. + 1.];
+                        // Physical pixel coordinates, like Shadertoy's iMouse, so the sign
+                        // trick below (always non-negative) can encode button state.
+                        playground.uniforms.mouse.x = position.x as f32;
+                        playground.uniforms.mouse.y = position.y as f32;
+                    }
+                    WindowEvent::MouseInput {
+                        state,
+                        button: winit::event::MouseButton::Left,
+                        ..
+                    } => {
+                        // zw pins to the position at the moment of press; only the sign
+                        // changes on release, matching Shadertoy's iMouse.
+                        if *state == winit::event::ElementState::Pressed {
+                            playground.uniforms.mouse.z = playground.uniforms.mouse.x;
+                            playground.uniforms.mouse.w = playground.uniforms.mouse.y;
+                        } else {
+                            playground.uniforms.mouse.z = -playground.uniforms.mouse.z.abs();
+                            playground.uniforms.mouse.w = -playground.uniforms.mouse.w.abs();
+                        }
                     }
                     // WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
                     //     playground.resize(new_inner_size)
                     // }
+                    WindowEvent::KeyboardInput {
+                        event: key_event, ..
+                    } => {
+                        if key_event.state != winit::event::ElementState::Pressed
+                            || key_event.repeat
+                        {
+                            return;
+                        }
+                        match key_event.physical_key {
+                            PhysicalKey::Code(KeyCode::Space) => {
+                                paused = !paused;
+                                if paused {
+                                    paused_time = instant.elapsed().as_secs_f32();
+                                } else {
+                                    instant = Instant::now() - Duration::from_secs_f32(paused_time);
+                                }
+                            }
+                            PhysicalKey::Code(KeyCode::ArrowLeft) if paused => {
+                                paused_time = (paused_time - SCRUB_STEP_SECS).max(0.);
+                            }
+                            PhysicalKey::Code(KeyCode::ArrowRight) if paused => {
+                                paused_time += SCRUB_STEP_SECS;
+                            }
+                            PhysicalKey::Code(KeyCode::F11) => {
+                                let window = playground
+                                    .window
+                                    .expect("the live event loop always has a window");
+                                window.set_fullscreen(if window.fullscreen().is_some() {
+                                    None
+                                } else {
+                                    Some(Fullscreen::Borderless(None))
+                                });
+                            }
+                            PhysicalKey::Code(KeyCode::KeyS) => {
+                                take_screenshot = true;
+                            }
+                            _ => {}
+                        }
+                    }
                     WindowEvent::RedrawRequested => {
-                        let output_frame = playground.surface.get_current_texture();
+                        let output_frame = playground
+                            .surface
+                            .as_ref()
+                            .expect("the live event loop always has a surface")
+                            .get_current_texture();
 
                         if output_frame.is_err() {
                             return;
                         }
 
                         let output = output_frame.unwrap();
-                        let view = output
-                            .texture
-                            .create_view(&wgpu::TextureViewDescriptor::default());
 
-                        playground.uniforms.time = instant.elapsed().as_secs_f32();
-                        queue.write_buffer(&uniforms_buffer, 0, playground.uniforms.as_bytes());
+                        let time = if paused {
+                            paused_time
+                        } else {
+                            instant.elapsed().as_secs_f32()
+                        };
+                        let frame = frame_counter;
+                        frame_counter += 1;
+                        let size =
+                            PhysicalSize::new(output.texture.width(), output.texture.height());
 
-                        let mut encoder = playground
-                            .device
-                            .create_command_encoder(&CommandEncoderDescriptor { label: None });
+                        let target = playground.render_frame(time, frame, size);
 
-                        {
-                            let mut render_pass =
-                                encoder.begin_render_pass(&RenderPassDescriptor {
-                                    label: None,
-                                    color_attachments: &[Some(RenderPassColorAttachment {
-                                        view: &view,
-                                        resolve_target: None,
-                                        ops: Operations {
-                                            load: LoadOp::Clear(wgpu::Color::BLACK),
-                                            store: wgpu::StoreOp::Store,
-                                        },
-                                    })],
-                                    depth_stencil_attachment: None,
-                                    timestamp_writes: None,
-                                    occlusion_query_set: None,
-                                });
-                            render_pass.set_pipeline(&playground.render_pipeline);
-                            render_pass.set_bind_group(0, &uniforms_buffer_bind_group, &[]);
-                            render_pass.draw(0..3, 0..1);
+                        if take_screenshot {
+                            take_screenshot = false;
+                            let timestamp = std::time::SystemTime::now()
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .unwrap()
+                                .as_secs();
+                            let path = PathBuf::from(format!("screenshot-{}.png", timestamp));
+                            match playground.read_back(target, size).save(&path) {
+                                Ok(()) => println!("Saved screenshot to {:?}", path),
+                                Err(e) => println!("Could not save {:?}: {}", path, e),
+                            }
                         }
 
-                        queue.submit(std::iter::once(encoder.finish()));
+                        // render_frame rendered into an offscreen target (so --render-to
+                        // can read it back identically); blit it into the swapchain.
+                        let mut encoder = playground
+                            .device
+                            .create_command_encoder(&CommandEncoderDescriptor { label: None });
+                        encoder.copy_texture_to_texture(
+                            wgpu::TexelCopyTextureInfo {
+                                texture: target,
+                                mip_level: 0,
+                                origin: wgpu::Origin3d::ZERO,
+                                aspect: wgpu::TextureAspect::All,
+                            },
+                            wgpu::TexelCopyTextureInfo {
+                                texture: &output.texture,
+                                mip_level: 0,
+                                origin: wgpu::Origin3d::ZERO,
+                                aspect: wgpu::TextureAspect::All,
+                            },
+                            Extent3d {
+                                width: size.width.max(1),
+                                height: size.height.max(1),
+                                depth_or_array_layers: 1,
+                            },
+                        );
+                        playground.queue.submit(std::iter::once(encoder.finish()));
                         output.present();
                     }
 
@@ -429,7 +1013,14 @@ impl<'window> Playground<'window> {
                     // Update last frame time for the next iteration
                     last_frame_time = Instant::now();
                     // println!("FPS: {:>6.2}", 1_000_000.0 / elapsed.as_micros() as f64);
-                    playground.window.request_redraw();
+                    // Freeze per-frame motion too while paused, not just the absolute
+                    // clock, so buffer passes driven by iTimeDelta actually stop.
+                    playground.uniforms.time_delta =
+                        if paused { 0. } else { elapsed.as_secs_f32() };
+                    playground
+                        .window
+                        .expect("the live event loop always has a window")
+                        .request_redraw();
                 }
             }
             _ => {}
@@ -437,6 +1028,232 @@ impl<'window> Playground<'window> {
             eprintln!("Error: {e}");
         }
     }
+
+    /// Builds everything shared between the live window and `--render-to` paths: the wgpu
+    /// instance/adapter/device, vertex shader, uniform buffer/layout, both paired bind-group
+    /// layouts, pipeline layout, image/buffer passes, channels and sampler. `window` selects
+    /// which path this is: `Some` creates a surface from it and derives `render_format` from
+    /// its capabilities, leaving `surface_config` and `watcher` for the live event loop to
+    /// fill in once it exists; `None` renders to a fixed `Rgba8UnormSrgb` target (so the
+    /// readback in `--render-to` mode can assume 4 bytes per pixel) with no surface or
+    /// watcher. Returns `None` if a shader file could not be read or preprocessed, or a
+    /// channel image could not be loaded (already reported via println).
+    fn build(
+        opts: &Opts,
+        window: Option<&'window Window>,
+        size: PhysicalSize<u32>,
+    ) -> Option<Playground<'window>> {
+        let dx12_shader_compiler = match (&opts.dxc_path, &opts.dxil_path) {
+            (Some(dxc_path), Some(dxil_path)) => wgpu::Dx12Compiler::Dxc {
+                dxc_path: Some(dxc_path.clone()),
+                dxil_path: Some(dxil_path.clone()),
+            },
+            _ => wgpu::Dx12Compiler::Fxc,
+        };
+
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: opts.backend.to_backends(),
+            dx12_shader_compiler,
+            ..Default::default()
+        });
+
+        let surface = window.map(|window| instance.create_surface(window).unwrap());
+        let (adapter, device, queue) = block_on(Self::get_async_stuff(
+            &instance,
+            surface.as_ref(),
+            opts.downlevel,
+        ));
+
+        let adapter_info = adapter.get_info();
+        println!(
+            "Using adapter: {} ({:?})",
+            adapter_info.name, adapter_info.backend
+        );
+
+        let vertex_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Vertex shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("./vertex.wgsl").into()),
+        });
+
+        let uniforms = Uniforms::default();
+
+        let uniforms_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: None,
+            contents: &uniforms.as_bytes(),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let uniforms_buffer_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                count: None,
+                ty: wgpu::BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+            }],
+        });
+
+        let buffers_bind_group_layout =
+            paired_texture_bind_group_layout(&device, opts.buffers.len());
+        let channels_bind_group_layout =
+            paired_texture_bind_group_layout(&device, opts.channels.len());
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[
+                &uniforms_buffer_layout,
+                &buffers_bind_group_layout,
+                &channels_bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        let render_format = match &surface {
+            Some(surface) => surface.get_capabilities(&adapter).formats[0],
+            None => TextureFormat::Rgba8UnormSrgb,
+        };
+
+        let mut touched = HashSet::new();
+
+        let render_pipeline = match Self::create_pipeline(
+            &device,
+            &vertex_shader_module,
+            &pipeline_layout,
+            render_format,
+            &opts.wgsl_file,
+        ) {
+            Ok((render_pipeline, image_touched)) => {
+                touched.extend(image_touched);
+                render_pipeline
+            }
+            Err(e) => {
+                println!("Could not start due to error: {}", &e);
+                return None;
+            }
+        };
+
+        let mut buffers = Vec::with_capacity(opts.buffers.len());
+        for shader_path in &opts.buffers {
+            match BufferPass::new(
+                &device,
+                &vertex_shader_module,
+                &pipeline_layout,
+                render_format,
+                size,
+                shader_path.clone(),
+            ) {
+                Ok((buffer, buffer_touched)) => {
+                    touched.extend(buffer_touched);
+                    buffers.push(buffer);
+                }
+                Err(e) => {
+                    println!("Could not start due to error: {}", &e);
+                    return None;
+                }
+            }
+        }
+
+        let mut channels = Vec::with_capacity(opts.channels.len());
+        for channel_path in &opts.channels {
+            match Channel::load(&device, &queue, channel_path.clone()) {
+                Ok(channel) => {
+                    touched.insert(channel.path.clone());
+                    channels.push(channel);
+                }
+                Err(e) => {
+                    println!("Could not start due to error: {}", &e);
+                    return None;
+                }
+            }
+        }
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let channels_bind_group =
+            channels_bind_group(&device, &channels_bind_group_layout, &sampler, &channels);
+
+        let uniforms_buffer_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: None,
+            layout: &uniforms_buffer_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: uniforms_buffer.as_entire_binding(),
+            }],
+        });
+
+        let render_target = create_texture(
+            &device,
+            render_format,
+            size,
+            TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        );
+
+        Some(Playground {
+            image_shader_path: opts.wgsl_file.clone(),
+            render_pipeline,
+            buffers,
+            channels,
+            sampler,
+            buffers_bind_group_layout,
+            channels_bind_group_layout,
+            channels_bind_group,
+            window,
+            device,
+            queue,
+            render_format,
+            pipeline_layout,
+            vertex_shader_module,
+            surface_config: None,
+            surface,
+            watcher: None,
+            watched_paths: touched,
+            render_target,
+            uniforms_buffer,
+            uniforms_buffer_bind_group,
+            uniforms,
+        })
+    }
+
+    /// Renders `opts.frames` frames at `opts.fps` to sequential PNGs in `dir`, bypassing
+    /// the winit window and swapchain entirely.
+    fn render_offline(opts: &Opts, dir: &Path) {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            println!("Could not create {:?}: {}", dir, e);
+            return;
+        }
+
+        let size = PhysicalSize::new(opts.size.0, opts.size.1);
+        let mut playground = match Self::build(opts, None, size) {
+            Some(playground) => playground,
+            None => return,
+        };
+
+        playground.uniforms.time_delta = 1. / opts.fps;
+
+        for frame in 0..opts.frames {
+            let time = frame as f32 / opts.fps;
+            let target = playground.render_frame(time, frame, size);
+            let image = playground.read_back(target, size);
+
+            let frame_path = dir.join(format!("frame_{:05}.png", frame));
+            if let Err(e) = image.save(&frame_path) {
+                println!("Could not save {:?}: {}", frame_path, e);
+            }
+
+            println!("Rendered {:?} ({}/{})", frame_path, frame + 1, opts.frames);
+        }
+    }
 }
 
 fn main() {
@@ -0,0 +1,169 @@
+use std::{
+    collections::HashSet,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+/// Resolves `#include "path.wgsl"` directives starting from `entry_path`, recursively
+/// splicing the referenced files in place.
+///
+/// Returns the flattened source along with the canonical paths of every file that was
+/// touched (the entry file plus all of its includes, transitively), so callers can watch
+/// all of them for changes.
+pub fn flatten(entry_path: &Path) -> Result<(String, HashSet<PathBuf>), String> {
+    let mut touched = HashSet::new();
+    let mut stack = Vec::new();
+    let source = expand(entry_path, &mut touched, &mut stack)?;
+    Ok((source, touched))
+}
+
+fn expand(
+    path: &Path,
+    touched: &mut HashSet<PathBuf>,
+    stack: &mut Vec<PathBuf>,
+) -> Result<String, String> {
+    let mut retries_left = 25; // ~5s at 200ms, enough to ride out an editor's atomic rename
+    let contents = loop {
+        match read_to_string(path) {
+            Ok(s) => break s,
+            // A missing file is a typo'd or removed #include, not a transient overwrite;
+            // retrying would just hang forever instead of reporting the bad path.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(format!("could not read {:?}: {}", path, e));
+            }
+            Err(e) => {
+                if retries_left == 0 {
+                    return Err(format!("could not read {:?}: {}", path, e));
+                }
+                retries_left -= 1;
+                // If file is being overwritten, wait until it is available
+                spin_sleep::sleep(std::time::Duration::from_millis(200));
+            }
+        }
+    };
+
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| format!("could not resolve {:?}: {}", path, e))?;
+
+    if stack.contains(&canonical) {
+        return Err(format!(
+            "#include cycle detected: {:?} includes itself transitively",
+            path
+        ));
+    }
+
+    if !touched.insert(canonical.clone()) {
+        // Already included elsewhere in the tree; skip re-splicing its contents.
+        return Ok(String::new());
+    }
+
+    let dir = canonical
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    stack.push(canonical);
+
+    let mut expanded = String::with_capacity(contents.len());
+    for line in contents.lines() {
+        if let Some(include_path) = parse_include(line) {
+            let included = dir.join(include_path);
+            expanded.push_str(&expand(&included, touched, stack)?);
+            expanded.push('\n');
+        } else {
+            expanded.push_str(line);
+            expanded.push('\n');
+        }
+    }
+
+    stack.pop();
+
+    Ok(expanded)
+}
+
+/// Parses a `#include "path"` directive from a single line, returning the quoted path.
+fn parse_include(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix("#include")?;
+    let rest = rest.trim();
+    let rest = rest.strip_prefix('"')?;
+    rest.strip_suffix('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A scratch directory under the system temp dir, removed when dropped. Real files are
+    /// unavoidable here since `expand` reads and canonicalizes paths directly.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let dir =
+                std::env::temp_dir().join(format!("wgsl_playground_test_{}_{}", name, line!()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+
+        fn write(&self, name: &str, contents: &str) -> PathBuf {
+            let path = self.0.join(name);
+            fs::write(&path, contents).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn missing_include_is_an_error_not_a_hang() {
+        let dir = TempDir::new("missing_include");
+        let entry = dir.write("entry.wgsl", "#include \"does_not_exist.wgsl\"\n");
+
+        let err = flatten(&entry).unwrap_err();
+        assert!(
+            err.contains("could not read"),
+            "expected a missing-file error, got: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn diamond_include_is_spliced_once() {
+        let dir = TempDir::new("diamond_include");
+        dir.write("common.wgsl", "// common\n");
+        dir.write("b.wgsl", "#include \"common.wgsl\"\n");
+        dir.write("c.wgsl", "#include \"common.wgsl\"\n");
+        let entry = dir.write("entry.wgsl", "#include \"b.wgsl\"\n#include \"c.wgsl\"\n");
+
+        let (source, touched) = flatten(&entry).unwrap();
+        assert_eq!(
+            source.matches("// common").count(),
+            1,
+            "common.wgsl should only be spliced in once, got: {:?}",
+            source
+        );
+        // entry.wgsl, b.wgsl, c.wgsl, common.wgsl
+        assert_eq!(touched.len(), 4);
+    }
+
+    #[test]
+    fn real_cycle_is_an_error() {
+        let dir = TempDir::new("real_cycle");
+        dir.write("a.wgsl", "#include \"b.wgsl\"\n");
+        let entry = dir.write("b.wgsl", "#include \"a.wgsl\"\n");
+
+        let err = flatten(&entry).unwrap_err();
+        assert!(
+            err.contains("cycle"),
+            "expected a cycle error, got: {}",
+            err
+        );
+    }
+}